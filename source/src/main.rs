@@ -1,10 +1,26 @@
 use std::fs::File;
-use std::io::{self, Write};
-use std::time::Duration;
-use csv::ReaderBuilder;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use csv::{ReaderBuilder, WriterBuilder};
 use clap::{Parser, ValueEnum};
+use serialport::SerialPort;
 
-#[derive(Parser)]
+/// Rejects an empty `--delimiter`: both the transmit framing (`frame_bytes`)
+/// and the receive framing (`run_receive`) need at least one delimiter byte
+/// to agree on where a record ends, and an empty string can't provide one.
+fn parse_delimiter(s: &str) -> Result<String, String> {
+    if s.is_empty() {
+        Err("delimiter must not be empty".to_string())
+    } else {
+        Ok(s.to_string())
+    }
+}
+
+#[derive(Parser, Clone)]
 struct Cli {
     /// The COM port to use (e.g., COM1)
     #[clap(short, long, default_value = "COM1")]
@@ -18,6 +34,26 @@ struct Cli {
     #[clap(short, long, value_parser)]
     mode: Mode,
 
+    /// Number of data bits per character
+    #[clap(long, value_enum, default_value = "eight")]
+    data_bits: DataBitsArg,
+
+    /// Parity checking mode
+    #[clap(long, value_enum, default_value = "none")]
+    parity: ParityArg,
+
+    /// Number of stop bits
+    #[clap(long, value_enum, default_value = "one")]
+    stop_bits: StopBitsArg,
+
+    /// Flow control mode
+    #[clap(long, value_enum, default_value = "none")]
+    flow_control: FlowControlArg,
+
+    /// Read timeout in milliseconds
+    #[clap(long, default_value_t = 10)]
+    timeout_ms: u64,
+
     /// Path to the telemetry data CSV file (used only in file-based transmit mode)
     #[clap(short, long)]
     file_path: Option<String>,
@@ -31,88 +67,672 @@ struct Cli {
     loop_mode: bool,
 
     /// Custom delimiter for transmission (default is "\n")
-    #[clap(long, default_value = "\n")]
+    #[clap(long, default_value = "\n", value_parser = parse_delimiter)]
     delimiter: String,
+
+    /// Require this ACK token after each record, retransmitting until it arrives
+    #[clap(long)]
+    require_ack: Option<String>,
+
+    /// How long to wait for the ACK token before retransmitting
+    #[clap(long, default_value_t = 1000)]
+    ack_timeout_ms: u64,
+
+    /// Maximum number of retransmits per record before aborting
+    #[clap(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Append each received, delimiter-terminated record to this CSV file
+    #[clap(long)]
+    log_file: Option<String>,
+
+    /// Number of bytes to generate per loopback iteration (ignored if --bytes is set)
+    #[clap(long)]
+    length: Option<usize>,
+
+    /// Explicit payload to loop back, e.g. --bytes 222,173,190,239
+    #[clap(long, value_delimiter = ',')]
+    bytes: Option<Vec<u8>>,
+
+    /// Number of loopback iterations to run
+    #[clap(long, default_value_t = 1)]
+    iterations: u32,
+
+    /// Address to listen on for TCP bridge mode, e.g. 0.0.0.0:9000
+    #[clap(long)]
+    listen: Option<String>,
+
+    /// Frame encoding for transmit/receive: delimiter-terminated text, or
+    /// COBS-encoded binary frames terminated by a zero byte
+    #[clap(long, value_enum, default_value = "delimiter")]
+    framing: FramingArg,
+
+    /// Delay between transmitted records, in milliseconds
+    #[clap(long, default_value_t = 1000, conflicts_with = "rate")]
+    interval_ms: u64,
+
+    /// Convenience alias for --interval-ms: records to send per second
+    #[clap(long)]
+    rate: Option<f64>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
 enum Mode {
     Transmit,
     Receive,
+    /// Transmit and receive at the same time on two independent threads
+    Duplex,
+    /// Write a payload and read it back on the same port, verifying and benchmarking
+    Loopback,
+    /// Bridge the serial port to a TCP client, forwarding bytes both ways
+    TcpBridge,
+    /// List available serial ports and exit
+    ListPorts,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum DataBitsArg {
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+impl From<DataBitsArg> for serialport::DataBits {
+    fn from(value: DataBitsArg) -> Self {
+        match value {
+            DataBitsArg::Five => serialport::DataBits::Five,
+            DataBitsArg::Six => serialport::DataBits::Six,
+            DataBitsArg::Seven => serialport::DataBits::Seven,
+            DataBitsArg::Eight => serialport::DataBits::Eight,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ParityArg {
+    None,
+    Odd,
+    Even,
+}
+
+impl From<ParityArg> for serialport::Parity {
+    fn from(value: ParityArg) -> Self {
+        match value {
+            ParityArg::None => serialport::Parity::None,
+            ParityArg::Odd => serialport::Parity::Odd,
+            ParityArg::Even => serialport::Parity::Even,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum StopBitsArg {
+    One,
+    Two,
+}
+
+impl From<StopBitsArg> for serialport::StopBits {
+    fn from(value: StopBitsArg) -> Self {
+        match value {
+            StopBitsArg::One => serialport::StopBits::One,
+            StopBitsArg::Two => serialport::StopBits::Two,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum FramingArg {
+    Delimiter,
+    Cobs,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum FlowControlArg {
+    None,
+    Software,
+    Hardware,
+}
+
+impl From<FlowControlArg> for serialport::FlowControl {
+    fn from(value: FlowControlArg) -> Self {
+        match value {
+            FlowControlArg::None => serialport::FlowControl::None,
+            FlowControlArg::Software => serialport::FlowControl::Software,
+            FlowControlArg::Hardware => serialport::FlowControl::Hardware,
+        }
+    }
+}
+
+/// Encodes `data` with Consistent Overhead Byte Stuffing: every run of
+/// non-zero bytes is prefixed with a length byte, so the encoded output never
+/// contains a zero. Appending a single zero byte after the result gives a
+/// frame whose boundary a receiver can always find, even joining mid-stream.
+fn cobs_encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 254 + 1);
+    let mut code_index = 0;
+    let mut code: u8 = 1;
+    out.push(0); // placeholder, patched in below
+
+    for &byte in data {
+        if byte == 0 {
+            out[code_index] = code;
+            code = 1;
+            code_index = out.len();
+            out.push(0);
+        } else {
+            out.push(byte);
+            code += 1;
+            if code == 0xFF {
+                out[code_index] = code;
+                code = 1;
+                code_index = out.len();
+                out.push(0);
+            }
+        }
+    }
+    out[code_index] = code;
+    out
+}
+
+/// Decodes a COBS frame (without its trailing zero terminator) back to the
+/// original payload.
+fn cobs_decode(frame: &[u8]) -> Result<Vec<u8>, io::Error> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut i = 0;
+
+    while i < frame.len() {
+        let code = frame[i] as usize;
+        if code == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unexpected zero byte inside COBS frame"));
+        }
+
+        let start = i + 1;
+        let end = start + code - 1;
+        if end > frame.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated COBS frame"));
+        }
+
+        out.extend_from_slice(&frame[start..end]);
+        i = end;
+
+        if code != 0xFF && i < frame.len() {
+            out.push(0);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Builds the on-wire bytes for `record` according to `--framing`: plain text
+/// terminated by `--delimiter`, or a COBS-encoded frame terminated by a zero
+/// byte.
+fn frame_bytes(record: &str, args: &Cli) -> Vec<u8> {
+    match args.framing {
+        FramingArg::Delimiter => {
+            let mut frame = record.as_bytes().to_vec();
+            frame.extend_from_slice(args.delimiter.as_bytes());
+            frame
+        }
+        FramingArg::Cobs => {
+            let mut frame = cobs_encode(record.as_bytes());
+            frame.push(0);
+            frame
+        }
+    }
+}
+
+/// Writes `record` as a single framed write. If `--require-ack` is set,
+/// blocks until the ACK token is seen on the port, retransmitting the record
+/// up to `--max-retries` times before giving up.
+fn send_record(port: &mut dyn SerialPort, record: &str, args: &Cli) -> io::Result<()> {
+    let frame = frame_bytes(record, args);
+
+    let Some(token) = &args.require_ack else {
+        port.write_all(&frame)?;
+        return Ok(());
+    };
+
+    for attempt in 0..=args.max_retries {
+        port.write_all(&frame)?;
+
+        if wait_for_ack(port, token, args.ack_timeout_ms)? {
+            return Ok(());
+        }
+
+        eprintln!("No ACK received for record (attempt {}/{}): {}", attempt + 1, args.max_retries + 1, record);
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("No ACK received after {} retries: {}", args.max_retries, record),
+    ))
+}
+
+/// Accumulates bytes read from `port` until `token` appears in the buffer or
+/// `timeout_ms` elapses. Per-read timeouts are non-fatal; the wait continues
+/// until the overall deadline passes.
+fn wait_for_ack(port: &mut dyn SerialPort, token: &str, timeout_ms: u64) -> io::Result<bool> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let port_timeout = port.timeout();
+    let mut buf = [0u8; 256];
+    let mut acc = String::new();
+
+    let result = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break Ok(false);
+        }
+
+        // Clamp the port's own read timeout (`--timeout-ms`) to whatever's
+        // left of the ACK deadline, so a single blocking `port.read` can't
+        // overshoot `--ack-timeout-ms` even if the port timeout is
+        // configured larger than it.
+        if let Err(e) = port.set_timeout(remaining.min(port_timeout)) {
+            break Err(io::Error::new(io::ErrorKind::Other, e.to_string()));
+        }
+
+        match port.read(&mut buf) {
+            Ok(t) => {
+                acc.push_str(&String::from_utf8_lossy(&buf[..t]));
+                if acc.contains(token) {
+                    break Ok(true);
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => break Err(e),
+        }
+    };
+
+    let _ = port.set_timeout(port_timeout);
+    result
+}
+
+/// Returns the delay to sleep between transmitted records: `--rate` (records
+/// per second) if given and greater than zero, otherwise `--interval-ms`.
+fn transmit_interval(args: &Cli) -> Duration {
+    match args.rate {
+        Some(rate) if rate > 0.0 => Duration::from_secs_f64(1.0 / rate),
+        _ => Duration::from_millis(args.interval_ms),
+    }
+}
+
+/// Runs the transmit loop (direct `--send` or CSV playback) until `--loop-mode`
+/// is exhausted or `shutdown` is signalled. `prefix` is printed before each
+/// status line so duplex mode's interleaved console stays readable.
+fn run_transmit(port: &mut dyn SerialPort, args: &Cli, shutdown: &AtomicBool, prefix: &str) -> io::Result<()> {
+    // Check if `send` option is provided
+    if let Some(data) = &args.send {
+        // Directly send the specified data
+        loop {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+
+            send_record(port, data, args)?;
+            println!("{}Sent: {}", prefix, data);
+
+            // Exit loop if loop_mode is not enabled
+            if !args.loop_mode {
+                break;
+            }
+
+            // Add a delay to simulate transmission interval
+            std::thread::sleep(transmit_interval(args));
+        }
+    } else if let Some(file_path) = &args.file_path {
+        // Open the CSV file for reading and send each record
+        loop {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let file = File::open(file_path).expect("Failed to open telemetry data CSV file");
+            let mut csv_reader = ReaderBuilder::new()
+                .has_headers(true)
+                .from_reader(file);
+
+            for result in csv_reader.records() {
+                if shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let record = result.expect("Failed to read CSV record");
+                let telemetry_data: String = record.iter().map(|field| field.to_string()).collect::<Vec<_>>().join(",");
+
+                send_record(port, &telemetry_data, args)?;
+                println!("{}Sent: {}", prefix, telemetry_data);
+
+                std::thread::sleep(transmit_interval(args));
+            }
+
+            if !args.loop_mode {
+                break;
+            }
+        }
+    } else {
+        eprintln!("Error: Either --file_path or --send must be provided in transmit mode.");
+    }
+
+    Ok(())
+}
+
+/// Returns seconds.milliseconds since the Unix epoch, used to timestamp
+/// received records.
+fn now_timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{}.{:03}", since_epoch.as_secs(), since_epoch.subsec_millis())
+}
+
+/// Runs the receive loop, reading one framed record at a time from `port`
+/// (instead of dumping whatever raw bytes a single read call happens to
+/// return) and printing each as a timestamped line. With
+/// `--framing delimiter` (the default) a frame ends at the first byte of
+/// `--delimiter`; any further delimiter bytes are not stripped and stay at the
+/// start of the next record. With
+/// `--framing cobs` a frame ends at a zero byte and is COBS-decoded first, so
+/// a receiver can always resynchronize on the next zero byte even for binary
+/// payloads that could otherwise collide with the delimiter. `prefix` is
+/// printed before each line so duplex mode's interleaved console stays
+/// readable. If `args.log_file` is set, each record is also appended there as
+/// a CSV row: the timestamp followed by the record's comma-split fields.
+fn run_receive(port: &mut dyn SerialPort, shutdown: &AtomicBool, prefix: &str, args: &Cli) -> io::Result<()> {
+    let terminator = match args.framing {
+        FramingArg::Delimiter => *args.delimiter.as_bytes().first().unwrap_or(&b'\n'),
+        FramingArg::Cobs => 0,
+    };
+    let mut reader = BufReader::new(port);
+    let mut log_writer = args.log_file.as_ref().map(|path| {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("Failed to open log file");
+        WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(file)
+    });
+
+    // `frame` is only cleared once a complete record has been consumed.
+    // `read_until` can internally perform several `fill_buf`/`port.read` calls
+    // before it either finds the terminator or hits a timeout; on a timeout it
+    // returns with whatever it has accumulated so far still sitting in
+    // `frame`. Clearing `frame` on every loop iteration (instead of only after
+    // a successful emit) would silently drop that already-consumed prefix, so
+    // a `TimedOut` below resumes the *same* `read_until` call on the next
+    // iteration rather than restarting the record from scratch.
+    let mut frame: Vec<u8> = Vec::new();
+    while !shutdown.load(Ordering::SeqCst) {
+        match reader.read_until(terminator, &mut frame) {
+            Ok(0) => continue,
+            Ok(_) => {
+                if frame.last() != Some(&terminator) {
+                    // Underlying reader hit EOF before the terminator showed up;
+                    // keep what's been read so far and keep waiting for more.
+                    continue;
+                }
+                frame.pop();
+
+                if frame.is_empty() {
+                    frame.clear();
+                    continue;
+                }
+
+                let payload = match args.framing {
+                    FramingArg::Delimiter => frame.clone(),
+                    FramingArg::Cobs => match cobs_decode(&frame) {
+                        Ok(decoded) => decoded,
+                        Err(e) => {
+                            eprintln!("Dropping invalid COBS frame: {}", e);
+                            frame.clear();
+                            continue;
+                        }
+                    },
+                };
+
+                let line = String::from_utf8_lossy(&payload).to_string();
+                let fields: Vec<&str> = line.split(',').collect();
+                let timestamp = now_timestamp();
+
+                println!("{}[{}] {}", prefix, timestamp, line);
+
+                if let Some(writer) = log_writer.as_mut() {
+                    let mut record = vec![timestamp];
+                    record.extend(fields.iter().map(|field| field.to_string()));
+                    writer.write_record(&record).expect("Failed to write log record");
+                    writer.flush().expect("Failed to flush log file");
+                }
+
+                frame.clear();
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => continue,
+            Err(e) => eprintln!("{:?}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a payload and reads the same number of bytes back, verifying the
+/// echo matches and reporting aggregate timing and throughput over all
+/// iterations. Used to validate a physical loopback plug or a paired virtual
+/// port before trusting it for real transmission.
+fn run_loopback(port: &mut dyn SerialPort, args: &Cli, shutdown: &AtomicBool) -> io::Result<()> {
+    let payload: Vec<u8> = if let Some(bytes) = &args.bytes {
+        bytes.clone()
+    } else {
+        let length = args.length.unwrap_or(64);
+        (0..length).map(|i| (i % 256) as u8).collect()
+    };
+
+    let mut mismatches = 0u32;
+    let mut total_bytes = 0u64;
+    let start = Instant::now();
+
+    for iteration in 0..args.iterations {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+
+        port.write_all(&payload)?;
+
+        let mut received = vec![0u8; payload.len()];
+        let mut filled = 0;
+        while filled < received.len() && !shutdown.load(Ordering::SeqCst) {
+            match port.read(&mut received[filled..]) {
+                Ok(t) if t > 0 => filled += t,
+                Ok(_) => (),
+                Err(ref e) if e.kind() == io::ErrorKind::TimedOut => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if received != payload {
+            mismatches += 1;
+            eprintln!("Iteration {}: mismatch (expected {:?}, got {:?})", iteration + 1, payload, received);
+        }
+
+        total_bytes += payload.len() as u64 * 2;
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let throughput = if elapsed > 0.0 { total_bytes as f64 / elapsed } else { 0.0 };
+
+    println!(
+        "Loopback complete: {} iterations, {} mismatches, {} bytes total, {:.3}s elapsed, {:.1} bytes/sec",
+        args.iterations, mismatches, total_bytes, elapsed, throughput
+    );
+
+    if mismatches > 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} of {} loopback iterations mismatched", mismatches, args.iterations),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Accepts a single TCP client on `listen_addr` and forwards bytes
+/// bidirectionally between it and `port` on two relay threads, so a
+/// visualization or analysis tool on another machine can consume the
+/// emulated telemetry stream (and inject commands back) without direct
+/// access to the COM port.
+fn run_tcp_bridge(port: Box<dyn SerialPort>, listen_addr: &str, args: &Cli, shutdown: &Arc<AtomicBool>) -> io::Result<()> {
+    let listener = TcpListener::bind(listen_addr)?;
+    println!("TCP bridge listening on {}", listen_addr);
+
+    let (stream, peer_addr) = listener.accept()?;
+    println!("TCP bridge client connected: {}", peer_addr);
+
+    // Without a read timeout the TCP-to-serial relay thread below would block
+    // in `read` indefinitely while the client is connected but idle, so it
+    // would never notice `shutdown` being set and Ctrl-C would hang until the
+    // remote end disconnected on its own.
+    stream
+        .set_read_timeout(Some(Duration::from_millis(args.timeout_ms)))
+        .expect("Failed to set TCP read timeout");
+
+    let mut serial_reader = port.try_clone().expect("Failed to clone serial port for TCP bridge");
+    let mut serial_writer = port;
+    let mut tcp_reader = stream.try_clone().expect("Failed to clone TCP stream for TCP bridge");
+    let mut tcp_writer = stream;
+
+    let serial_to_tcp_shutdown = shutdown.clone();
+    let tcp_to_serial_shutdown = shutdown.clone();
+
+    // Both closures store `true` into the shared shutdown flag on every exit
+    // path, success or error, so a failure on one side of the bridge (e.g.
+    // the USB-serial adapter being unplugged) always tells the other side's
+    // thread to stop instead of leaving it parked until Ctrl-C or the remote
+    // client disconnects on its own.
+    let serial_to_tcp = thread::spawn(move || -> io::Result<()> {
+        let result = (|| -> io::Result<()> {
+            let mut buf = [0u8; 1024];
+            while !serial_to_tcp_shutdown.load(Ordering::SeqCst) {
+                match serial_reader.read(&mut buf) {
+                    Ok(t) if t > 0 => tcp_writer.write_all(&buf[..t])?,
+                    Ok(_) => (),
+                    Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        })();
+        serial_to_tcp_shutdown.store(true, Ordering::SeqCst);
+        result
+    });
+
+    let tcp_to_serial = thread::spawn(move || -> io::Result<()> {
+        let result = (|| -> io::Result<()> {
+            let mut buf = [0u8; 1024];
+            while !tcp_to_serial_shutdown.load(Ordering::SeqCst) {
+                match tcp_reader.read(&mut buf) {
+                    Ok(0) => break, // client disconnected
+                    Ok(t) => serial_writer.write_all(&buf[..t])?,
+                    Err(ref e) if e.kind() == io::ErrorKind::TimedOut || e.kind() == io::ErrorKind::WouldBlock => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        })();
+        tcp_to_serial_shutdown.store(true, Ordering::SeqCst);
+        result
+    });
+
+    tcp_to_serial.join().expect("TCP-to-serial relay thread panicked")?;
+    serial_to_tcp.join().expect("Serial-to-TCP relay thread panicked")?;
+
+    Ok(())
+}
+
+/// Lists available serial ports and, for USB devices, their VID/PID,
+/// manufacturer, and product strings, so users can discover which COM/tty
+/// device to target.
+fn run_list_ports() -> io::Result<()> {
+    let ports = serialport::available_ports().map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    if ports.is_empty() {
+        println!("No serial ports found.");
+        return Ok(());
+    }
+
+    for port in ports {
+        match port.port_type {
+            serialport::SerialPortType::UsbPort(info) => println!(
+                "{} - USB VID:PID={:04x}:{:04x} manufacturer={} product={} serial={}",
+                port.port_name,
+                info.vid,
+                info.pid,
+                info.manufacturer.as_deref().unwrap_or("unknown"),
+                info.product.as_deref().unwrap_or("unknown"),
+                info.serial_number.as_deref().unwrap_or("unknown"),
+            ),
+            serialport::SerialPortType::PciPort => println!("{} - PCI port", port.port_name),
+            serialport::SerialPortType::BluetoothPort => println!("{} - Bluetooth port", port.port_name),
+            serialport::SerialPortType::Unknown => println!("{} - unknown port type", port.port_name),
+        }
+    }
+
+    Ok(())
 }
 
 fn main() -> io::Result<()> {
     // Parse command line arguments
     let args = Cli::parse();
 
+    if args.mode == Mode::ListPorts {
+        return run_list_ports();
+    }
+
     // Set up the serial port
     let port_name = &args.com_port;
     let baud_rate = args.baud_rate;
     let mut port = serialport::new(port_name, baud_rate)
-        .timeout(Duration::from_millis(10))
+        .data_bits(args.data_bits.into())
+        .parity(args.parity.into())
+        .stop_bits(args.stop_bits.into())
+        .flow_control(args.flow_control.into())
+        .timeout(Duration::from_millis(args.timeout_ms))
         .open()
         .expect("Failed to open serial port");
 
+    let shutdown = Arc::new(AtomicBool::new(false));
+    {
+        let shutdown = shutdown.clone();
+        ctrlc::set_handler(move || shutdown.store(true, Ordering::SeqCst))
+            .expect("Failed to set Ctrl-C handler");
+    }
+
     match args.mode {
-        Mode::Transmit => {
-            // Check if `send` option is provided
-            if let Some(data) = args.send {
-                // Directly send the specified data
-                loop {
-                    port.write_all(data.as_bytes())?;
-                    port.write_all(args.delimiter.as_bytes())?; // Send custom delimiter
-                    println!("Sent: {}", data);
-
-                    // Exit loop if loop_mode is not enabled
-                    if !args.loop_mode {
-                        break;
-                    }
-
-                    // Add a delay to simulate transmission interval
-                    std::thread::sleep(Duration::from_secs(1));
-                }
-            } else if let Some(file_path) = args.file_path {
-                // Open the CSV file for reading and send each record
-                loop {
-                    let file = File::open(&file_path).expect("Failed to open telemetry data CSV file");
-                    let mut csv_reader = ReaderBuilder::new()
-                        .has_headers(true)
-                        .from_reader(file);
-
-                    for result in csv_reader.records() {
-                        let record = result.expect("Failed to read CSV record");
-                        let telemetry_data: String = record.iter().map(|field| field.to_string()).collect::<Vec<_>>().join(",");
-
-                        port.write_all(telemetry_data.as_bytes())?;
-                        port.write_all(args.delimiter.as_bytes())?;
-                        println!("Sent: {}", telemetry_data);
-
-                        std::thread::sleep(Duration::from_secs(1));
-                    }
-
-                    if !args.loop_mode {
-                        break;
-                    }
-                }
-            } else {
-                eprintln!("Error: Either --file_path or --send must be provided in transmit mode.");
-            }
-        }
+        Mode::Transmit => run_transmit(port.as_mut(), &args, &shutdown, "")?,
         Mode::Receive => {
-            let mut serial_buf: Vec<u8> = vec![0; 1000];
             println!("Receiving data on {} at {} baud:", port_name, baud_rate);
+            run_receive(port.as_mut(), &shutdown, "", &args)?
+        }
+        Mode::Duplex => {
+            let mut tx_port = port.try_clone().expect("Failed to clone serial port for duplex transmit");
+            let mut rx_port = port;
 
-            loop {
-                match port.read(serial_buf.as_mut_slice()) {
-                    Ok(t) => {
-                        io::stdout().write_all(&serial_buf[..t]).unwrap();
-                        io::stdout().flush().unwrap();
-                    }
-                    Err(ref e) if e.kind() == io::ErrorKind::TimedOut => (),
-                    Err(e) => eprintln!("{:?}", e),
-                }
-            }
+            let tx_args = args.clone();
+            let rx_args = args.clone();
+            let tx_shutdown = shutdown.clone();
+            let rx_shutdown = shutdown.clone();
+
+            println!("Duplex mode on {} at {} baud (Ctrl-C to stop):", port_name, baud_rate);
+
+            let tx_handle = thread::spawn(move || run_transmit(tx_port.as_mut(), &tx_args, &tx_shutdown, "[TX] "));
+            let rx_handle = thread::spawn(move || run_receive(rx_port.as_mut(), &rx_shutdown, "[RX] ", &rx_args));
+
+            tx_handle.join().expect("Transmit thread panicked")?;
+            rx_handle.join().expect("Receive thread panicked")?;
+        }
+        Mode::Loopback => run_loopback(port.as_mut(), &args, &shutdown)?,
+        Mode::TcpBridge => {
+            let listen_addr = args.listen.clone().expect("--listen is required in tcp-bridge mode");
+            run_tcp_bridge(port, &listen_addr, &args, &shutdown)?
         }
+        Mode::ListPorts => unreachable!("handled before opening the serial port"),
     }
 
     Ok(())